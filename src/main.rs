@@ -2,23 +2,59 @@ extern crate queues;
 
 use clap::{arg, command};
 use queues::*;
-use regex::Regex;
+use regex::{Regex, RegexSet};
 use std::fs::File;
 use std::io::prelude::BufRead;
-use std::io::{BufReader, BufWriter, Read, Write};
+use std::io::{BufReader, BufWriter, IsTerminal, Read, Write};
+use std::process::{Child, ChildStdout, Command, Stdio};
+use std::thread::JoinHandle;
 
 struct Arguments {
-    input: Box<dyn Read>,
+    input: Box<dyn Read + Send>,
     output: Box<dyn Write>,
     regexp: Vec<String>,
     before_lines: i32,
     after_lines: i32,
+    color_enabled: bool,
+    only_matching: bool,
+    group: Option<usize>,
+    fixed_strings: bool,
+    word_regexp: bool,
 }
 
+#[derive(Debug)]
+enum IawkError {
+    Io { context: String, source: std::io::Error },
+    Regex { context: String, source: regex::Error },
+}
+
+impl std::fmt::Display for IawkError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IawkError::Io { context, source } => write!(f, "iawk: {}: {}", context, source),
+            IawkError::Regex { context, source } => write!(f, "iawk: {}: {}", context, source),
+        }
+    }
+}
+
+impl std::error::Error for IawkError {}
+
 fn main() {
     let argument_matcher: clap::ArgMatches = setup();
-    let arguments = get_arguments(argument_matcher);
-    parse(arguments);
+    let exit_code = match get_arguments(argument_matcher).and_then(parse) {
+        Ok(matched_any) => {
+            if matched_any {
+                0
+            } else {
+                1
+            }
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            2
+        }
+    };
+    std::process::exit(exit_code);
 }
 
 fn setup() -> clap::ArgMatches {
@@ -61,22 +97,104 @@ fn setup() -> clap::ArgMatches {
             .default_missing_value("0")
             .required(false)
         )
+        .arg(
+            arg!(
+                -C --context <NUM> "Number of lines to include before and after (overridden by --before/--after)"
+            )
+            .value_parser(clap::value_parser!(i32))
+            .default_missing_value("0")
+            .required(false)
+        )
+        .arg(
+            arg!(
+                --decompress <MODE> "How to handle compressed input: auto, gzip or none"
+            )
+            .value_parser(["auto", "gzip", "none"])
+            .default_value("auto")
+            .required(false)
+        )
+        .arg(
+            arg!(
+                --color <MODE> "Highlight matches: auto, always or never"
+            )
+            .value_parser(["auto", "always", "never"])
+            .default_value("auto")
+            .required(false)
+        )
+        .arg(
+            arg!(
+                -O --"only-matching" "Print only the matched text instead of the whole line"
+            )
+            .action(clap::ArgAction::SetTrue)
+            .required(false)
+        )
+        .arg(
+            arg!(
+                --group <NUM> "Print only the Nth capture group of the matching line"
+            )
+            .value_parser(clap::value_parser!(usize))
+            .required(false)
+        )
+        .arg(
+            arg!(
+                --pre <CMD> "External command to preprocess input through before matching"
+            )
+            .required(false)
+        )
+        .arg(
+            arg!(
+                -F --"fixed-strings" "Treat each regexp as a literal string instead of a regular expression"
+            )
+            .action(clap::ArgAction::SetTrue)
+            .required(false)
+        )
+        .arg(
+            arg!(
+                -w --"word-regexp" "Only match at word boundaries"
+            )
+            .action(clap::ArgAction::SetTrue)
+            .required(false)
+        )
         .get_matches()
 }
 
-fn get_arguments(argument_matcher: clap::ArgMatches) -> Arguments {
-    let input: Box<dyn Read> = get_input(&argument_matcher);
-    let output: Box<dyn Write> = get_output(&argument_matcher);
+fn get_arguments(argument_matcher: clap::ArgMatches) -> Result<Arguments, IawkError> {
+    let input: Box<dyn Read + Send> = get_input(&argument_matcher)?;
+    let output: Box<dyn Write> = get_output(&argument_matcher)?;
     let regexp: Vec<String> = get_regexp(&argument_matcher);
-    let before_lines: i32 = get_argument_value(&argument_matcher, "before", &0);
-    let after_lines: i32 = get_argument_value(&argument_matcher, "after", &0);
+    let (before_lines, after_lines) = get_context_lines(&argument_matcher);
+    let color_enabled: bool = get_color_enabled(&argument_matcher);
+    let only_matching: bool = argument_matcher.get_flag("only-matching");
+    let group: Option<usize> = argument_matcher.get_one::<usize>("group").copied();
+    let fixed_strings: bool = argument_matcher.get_flag("fixed-strings");
+    let word_regexp: bool = argument_matcher.get_flag("word-regexp");
 
-    Arguments {
+    Ok(Arguments {
         input,
         output,
         regexp,
         before_lines,
         after_lines,
+        color_enabled,
+        only_matching,
+        group,
+        fixed_strings,
+        word_regexp,
+    })
+}
+
+fn get_color_enabled(argument_matcher: &clap::ArgMatches) -> bool {
+    let mode = argument_matcher
+        .get_one::<String>("color")
+        .map(|v| v.as_str())
+        .unwrap_or("auto");
+    match mode {
+        "always" => true,
+        "never" => false,
+        _ => {
+            argument_matcher.get_one::<String>("output").is_none()
+                && std::io::stdout().is_terminal()
+        }
     }
 }
 
@@ -88,24 +206,135 @@ fn get_regexp(argument_matcher: &clap::ArgMatches) -> Vec<String> {
         .collect::<Vec<String>>()
 }
 
-fn get_output(argument_matcher: &clap::ArgMatches) -> Box<dyn Write> {
+fn get_output(argument_matcher: &clap::ArgMatches) -> Result<Box<dyn Write>, IawkError> {
     let mut output: Box<dyn Write> = Box::new(std::io::stdout());
     if let Some(output_path) = argument_matcher.get_one::<String>("output") {
-        let file_result = Box::new(File::create(output_path).unwrap());
-        let writer = BufWriter::new(file_result);
-        output = Box::new(writer);
+        let file = File::create(output_path).map_err(|e| IawkError::Io {
+            context: format!("could not create output file '{}'", output_path),
+            source: e,
+        })?;
+        output = Box::new(BufWriter::new(file));
     }
-    output
+    Ok(output)
 }
 
-fn get_input(argument_matcher: &clap::ArgMatches) -> Box<dyn Read> {
-    let mut input: Box<dyn Read> = Box::new(std::io::stdin());
+fn get_input(argument_matcher: &clap::ArgMatches) -> Result<Box<dyn Read + Send>, IawkError> {
+    let mut input: Box<dyn Read + Send> = Box::new(std::io::stdin());
     if let Some(input_path) = argument_matcher.get_one::<String>("input") {
-        let file_result = Box::new(File::open(input_path).unwrap());
-        let reader = BufReader::new(file_result);
-        input = Box::new(reader);
+        let file = File::open(input_path).map_err(|e| IawkError::Io {
+            context: format!("could not open input file '{}'", input_path),
+            source: e,
+        })?;
+        input = Box::new(BufReader::new(file));
+    }
+    let mode = argument_matcher
+        .get_one::<String>("decompress")
+        .map(|v| v.as_str())
+        .unwrap_or("auto");
+    input = wrap_decompressed(input, mode);
+    if let Some(pre_command) = argument_matcher.get_one::<String>("pre") {
+        input = wrap_preprocessor(input, pre_command)?;
+    }
+    Ok(input)
+}
+
+fn wrap_preprocessor(mut input: Box<dyn Read + Send>, command: &str) -> Result<Box<dyn Read + Send>, IawkError> {
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| IawkError::Io {
+            context: format!("could not spawn preprocessor '{}'", command),
+            source: e,
+        })?;
+
+    let mut child_stdin = child.stdin.take().expect("preprocessor stdin was piped");
+    let feed_handle = std::thread::spawn(move || {
+        let _ = std::io::copy(&mut input, &mut child_stdin);
+    });
+
+    let mut child_stderr = child.stderr.take().expect("preprocessor stderr was piped");
+    let stderr_handle = std::thread::spawn(move || {
+        let mut captured = Vec::new();
+        let _ = child_stderr.read_to_end(&mut captured);
+        captured
+    });
+
+    let child_stdout = child.stdout.take().expect("preprocessor stdout was piped");
+
+    Ok(Box::new(PreprocessorReader {
+        stdout: child_stdout,
+        child,
+        feed_handle: Some(feed_handle),
+        stderr_handle: Some(stderr_handle),
+        finished: false,
+    }))
+}
+
+struct PreprocessorReader {
+    stdout: ChildStdout,
+    child: Child,
+    feed_handle: Option<JoinHandle<()>>,
+    stderr_handle: Option<JoinHandle<Vec<u8>>>,
+    finished: bool,
+}
+
+impl Read for PreprocessorReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read_bytes = self.stdout.read(buf)?;
+        if read_bytes == 0 && !self.finished {
+            self.finished = true;
+            if let Some(feed_handle) = self.feed_handle.take() {
+                let _ = feed_handle.join();
+            }
+            let status = self.child.wait()?;
+            if !status.success() {
+                let stderr_output = self
+                    .stderr_handle
+                    .take()
+                    .and_then(|handle| handle.join().ok())
+                    .unwrap_or_default();
+                return Err(std::io::Error::other(format!(
+                    "preprocessor exited with {}: {}",
+                    status,
+                    String::from_utf8_lossy(&stderr_output)
+                )));
+            }
+        }
+        Ok(read_bytes)
+    }
+}
+
+fn wrap_decompressed(input: Box<dyn Read + Send>, mode: &str) -> Box<dyn Read + Send> {
+    match mode {
+        "none" => input,
+        "gzip" => Box::new(flate2::read::GzDecoder::new(input)),
+        _ => detect_and_decompress(input),
+    }
+}
+
+fn detect_and_decompress(input: Box<dyn Read + Send>) -> Box<dyn Read + Send> {
+    let mut peekable = BufReader::new(input);
+    let (is_gzip, is_bzip2, is_xz) = {
+        let header = peekable.fill_buf().unwrap_or(&[]);
+        (
+            header.starts_with(&[0x1f, 0x8b]),
+            header.starts_with(b"BZh"),
+            header.starts_with(&[0xfd, b'7', b'z', b'X', b'Z']),
+        )
+    };
+    if is_gzip {
+        Box::new(flate2::read::GzDecoder::new(peekable))
+    } else if is_bzip2 {
+        Box::new(bzip2::read::BzDecoder::new(peekable))
+    } else if is_xz {
+        Box::new(xz2::read::XzDecoder::new(peekable))
+    } else {
+        Box::new(peekable)
     }
-    input
 }
 
 fn get_argument_value(
@@ -118,61 +347,254 @@ fn get_argument_value(
         .unwrap_or(default)
 }
 
-fn parse(arguments: Arguments) {
-    let mut before_buffer: CircularBuffer<String> =
-        CircularBuffer::<String>::new(arguments.before_lines as usize);
-    let mut after_line: i32 = 0;
+fn get_context_lines(argument_matcher: &clap::ArgMatches) -> (i32, i32) {
+    let context = argument_matcher.get_one::<i32>("context").copied();
+    let before_lines = if argument_matcher.contains_id("before") {
+        get_argument_value(argument_matcher, "before", &0)
+    } else {
+        context.unwrap_or(0)
+    };
+    let after_lines = if argument_matcher.contains_id("after") {
+        get_argument_value(argument_matcher, "after", &0)
+    } else {
+        context.unwrap_or(0)
+    };
+    (before_lines, after_lines)
+}
+
+fn parse(arguments: Arguments) -> Result<bool, IawkError> {
+    let mut before_buffer: CircularBuffer<(u64, String)> =
+        CircularBuffer::<(u64, String)>::new(arguments.before_lines as usize);
+    let mut after_remaining: i32 = 0;
+    let mut matched_any = false;
+    let mut last_printed: Option<u64> = None;
     let input = arguments.input;
     let mut output = arguments.output;
     let reader = BufReader::new(input);
-    let regexps = arguments
-        .regexp
-        .into_iter()
-        .map(|r| Regex::new(&r).expect("Invalid regular expression"))
-        .collect::<Vec<Regex>>();
-    for line in reader.lines() {
+    let patterns = transform_patterns(
+        &arguments.regexp,
+        arguments.fixed_strings,
+        arguments.word_regexp,
+    );
+    let set = build_regex_set(&patterns)?;
+    let regexes = build_regexes(&patterns)?;
+    let match_output = MatchOutput {
+        only_matching: arguments.only_matching,
+        group: arguments.group,
+        color_enabled: arguments.color_enabled,
+    };
+    for (index, line) in reader.lines().enumerate() {
+        let line_number = index as u64 + 1;
         match line {
             Ok(line) => {
-                if after_line <= 0 && is_match_any(&line, &regexps) {
-                    output_before_lines(&mut before_buffer, &mut output);
-                    output_line(&line, &mut output);
-                    after_line = arguments.after_lines;
-                } else if after_line > 0 {
-                    output_line(&line, &mut output);
-                    after_line -= 1;
+                if set.is_match(&line) {
+                    matched_any = true;
+                    if after_remaining <= 0 {
+                        output_before_lines(&mut before_buffer, &mut output, &mut last_printed)?;
+                    }
+                    print_matched_line(
+                        line_number,
+                        &line,
+                        &regexes,
+                        &match_output,
+                        &mut last_printed,
+                        &mut output,
+                    )?;
+                    after_remaining = arguments.after_lines;
+                } else if after_remaining > 0 {
+                    print_tracked_line(line_number, &line, &mut last_printed, &mut output)?;
+                    after_remaining -= 1;
                 } else {
-                    let _ = before_buffer.add(line);
+                    let _ = before_buffer.add((line_number, line));
                 }
             }
             Err(e) => {
-                std::io::stderr()
-                    .write_all(format!("Error reading line: {}", e).as_bytes())
-                    .unwrap();
+                return Err(IawkError::Io {
+                    context: "error reading line".to_string(),
+                    source: e,
+                });
+            }
+        }
+    }
+    Ok(matched_any)
+}
+
+fn write_separator_if_gap(
+    line_number: u64,
+    last_printed: Option<u64>,
+    output: &mut dyn Write,
+) -> Result<(), IawkError> {
+    if let Some(last) = last_printed {
+        if line_number != last + 1 {
+            output_line(&"--".to_string(), output)?;
+        }
+    }
+    Ok(())
+}
+
+fn print_tracked_line(
+    line_number: u64,
+    line: &str,
+    last_printed: &mut Option<u64>,
+    output: &mut dyn Write,
+) -> Result<(), IawkError> {
+    write_separator_if_gap(line_number, *last_printed, output)?;
+    output_line(&line.to_string(), output)?;
+    *last_printed = Some(line_number);
+    Ok(())
+}
+
+struct MatchOutput {
+    only_matching: bool,
+    group: Option<usize>,
+    color_enabled: bool,
+}
+
+fn print_matched_line(
+    line_number: u64,
+    line: &str,
+    regexes: &[Regex],
+    match_output: &MatchOutput,
+    last_printed: &mut Option<u64>,
+    output: &mut dyn Write,
+) -> Result<(), IawkError> {
+    write_separator_if_gap(line_number, *last_printed, output)?;
+    if match_output.only_matching || match_output.group.is_some() {
+        emit_only_matching(line, regexes, match_output.group, output)?;
+    } else if match_output.color_enabled {
+        output_line(&highlight_line(line, regexes), output)?;
+    } else {
+        output_line(&line.to_string(), output)?;
+    }
+    *last_printed = Some(line_number);
+    Ok(())
+}
+
+fn transform_patterns(regexp: &[String], fixed_strings: bool, word_regexp: bool) -> Vec<String> {
+    regexp
+        .iter()
+        .map(|pattern| {
+            let pattern = if fixed_strings {
+                regex::escape(pattern)
+            } else {
+                pattern.clone()
+            };
+            if word_regexp {
+                format!("\\b(?:{})\\b", pattern)
+            } else {
+                pattern
             }
+        })
+        .collect()
+}
+
+fn build_regex_set(regexp: &[String]) -> Result<RegexSet, IawkError> {
+    RegexSet::new(regexp).map_err(|e| {
+        let bad_index = regexp
+            .iter()
+            .position(|pattern| regex::Regex::new(pattern).is_err())
+            .unwrap_or(0);
+        IawkError::Regex {
+            context: format!("invalid regular expression at index {}", bad_index),
+            source: e,
+        }
+    })
+}
+
+fn build_regexes(regexp: &[String]) -> Result<Vec<Regex>, IawkError> {
+    regexp
+        .iter()
+        .map(|r| {
+            Regex::new(r).map_err(|e| IawkError::Regex {
+                context: format!("invalid regular expression '{}'", r),
+                source: e,
+            })
+        })
+        .collect()
+}
+
+fn merged_match_spans(line: &str, regexes: &[Regex]) -> Vec<(usize, usize)> {
+    let mut spans: Vec<(usize, usize)> = regexes
+        .iter()
+        .flat_map(|regex| regex.find_iter(line))
+        .map(|m| (m.start(), m.end()))
+        .collect();
+    spans.sort_unstable();
+    let mut merged: Vec<(usize, usize)> = Vec::new();
+    for (start, end) in spans {
+        match merged.last_mut() {
+            Some((_, last_end)) if start <= *last_end => *last_end = (*last_end).max(end),
+            _ => merged.push((start, end)),
         }
     }
+    merged
 }
 
-fn is_match_any(line: &str, regexps: &Vec<Regex>) -> bool {
-    for regexp in regexps {
-        if regexp.is_match(line) {
-            return true;
+fn highlight_line(line: &str, regexes: &[Regex]) -> String {
+    let merged = merged_match_spans(line, regexes);
+    if merged.is_empty() {
+        return line.to_string();
+    }
+    let mut highlighted = String::new();
+    let mut cursor = 0;
+    for (start, end) in merged {
+        highlighted.push_str(&line[cursor..start]);
+        highlighted.push_str("\x1b[1;31m");
+        highlighted.push_str(&line[start..end]);
+        highlighted.push_str("\x1b[0m");
+        cursor = end;
+    }
+    highlighted.push_str(&line[cursor..]);
+    highlighted
+}
+
+fn emit_only_matching(
+    line: &str,
+    regexes: &[Regex],
+    group: Option<usize>,
+    output: &mut dyn Write,
+) -> Result<(), IawkError> {
+    match group {
+        Some(group_index) => {
+            let Some(regex) = regexes.iter().find(|regex| regex.is_match(line)) else {
+                return Ok(());
+            };
+            if let Some(captures) = regex.captures(line) {
+                if let Some(matched) = captures.get(group_index) {
+                    output_line(&matched.as_str().to_string(), output)?;
+                }
+            }
+        }
+        None => {
+            for (start, end) in merged_match_spans(line, regexes) {
+                output_line(&line[start..end].to_string(), output)?;
+            }
         }
     }
-    false
+    Ok(())
 }
 
-fn output_before_lines(before_buffer: &mut CircularBuffer<String>, output: &mut Box<dyn Write>) {
+fn output_before_lines(
+    before_buffer: &mut CircularBuffer<(u64, String)>,
+    output: &mut Box<dyn Write>,
+    last_printed: &mut Option<u64>,
+) -> Result<(), IawkError> {
     while before_buffer.size() > 0 {
-        if let Ok(before_line) = before_buffer.remove() {
-            output_line(&before_line, output);
+        if let Ok((line_number, before_line)) = before_buffer.remove() {
+            print_tracked_line(line_number, &before_line, last_printed, output)?;
         }
     }
+    Ok(())
 }
 
-fn output_line(line: &String, output: &mut dyn Write) {
-    output.write_all(line.as_bytes()).unwrap();
-    output.write_all(b"\n").unwrap();
+fn output_line(line: &String, output: &mut dyn Write) -> Result<(), IawkError> {
+    let write_error = |e| IawkError::Io {
+        context: "failed to write output".to_string(),
+        source: e,
+    };
+    output.write_all(line.as_bytes()).map_err(write_error)?;
+    output.write_all(b"\n").map_err(write_error)?;
+    Ok(())
 }
 
 #[cfg(test)]
@@ -186,7 +608,7 @@ mod tests {
         let result = "Test\n";
         let input = "Test".to_string();
         let mut output: Box<Vec<u8>> = Box::default();
-        output_line(&input, &mut output);
+        output_line(&input, &mut output).unwrap();
         assert_eq!(result.as_bytes(), output.as_slice());
     }
 
@@ -250,4 +672,126 @@ mod tests {
         let _ = output.stdout.as_slice().read_to_end(&mut read_data);
         assert_eq!(expected_data, read_data);
     }
+
+    #[test]
+    fn test_decompress_gzip_input() {
+        use flate2::write::GzEncoder;
+        use flate2::Compression;
+        use std::io::Write as _;
+
+        let gz_path = std::env::temp_dir().join("iawk_test_decompress_gzip_input.gz");
+        let file = File::create(&gz_path).unwrap();
+        let mut encoder = GzEncoder::new(file, Compression::default());
+        encoder.write_all(b"hello world needle\n").unwrap();
+        encoder.finish().unwrap();
+
+        let mut cmd = Command::cargo_bin("iawk").expect("Could not find iawk.");
+        cmd.arg("--regexp=needle");
+        cmd.arg(format!("--input={}", gz_path.display()));
+        cmd.assert().success();
+        let output = cmd.output().unwrap();
+        assert_eq!(b"hello world needle\n", output.stdout.as_slice());
+
+        let _ = std::fs::remove_file(&gz_path);
+    }
+
+    #[test]
+    fn test_color_always_highlights_match() {
+        let mut cmd = Command::cargo_bin("iawk").expect("Could not find iawk.");
+        cmd.arg("--regexp=def");
+        cmd.arg("--color=always");
+        cmd.write_stdin(String::from("abc\ndef\nghi"));
+        cmd.assert().success();
+        let output = cmd.output().unwrap();
+        assert_eq!(b"\x1b[1;31mdef\x1b[0m\n", output.stdout.as_slice());
+    }
+
+    #[test]
+    fn test_only_matching_emits_every_pattern() {
+        let mut cmd = Command::cargo_bin("iawk").expect("Could not find iawk.");
+        cmd.arg(r"--regexp=foo\d+");
+        cmd.arg(r"--regexp=bar\d+");
+        cmd.arg("--only-matching");
+        cmd.write_stdin(String::from("foo123 bar456"));
+        cmd.assert().success();
+        let output = cmd.output().unwrap();
+        assert_eq!(b"foo123\nbar456\n", output.stdout.as_slice());
+    }
+
+    #[test]
+    fn test_exit_code_no_match() {
+        let mut cmd = Command::cargo_bin("iawk").expect("Could not find iawk.");
+        cmd.arg("--regexp=zzz");
+        cmd.write_stdin(String::from("abc\ndef"));
+        cmd.assert().failure().code(1);
+    }
+
+    #[test]
+    fn test_exit_code_invalid_regex() {
+        let mut cmd = Command::cargo_bin("iawk").expect("Could not find iawk.");
+        cmd.arg("--regexp=[");
+        cmd.write_stdin(String::from("abc"));
+        cmd.assert().failure().code(2);
+    }
+
+    #[test]
+    fn test_exit_code_line_read_error() {
+        let mut cmd = Command::cargo_bin("iawk").expect("Could not find iawk.");
+        cmd.arg("--regexp=abc");
+        cmd.write_stdin(vec![b'a', b'b', b'c', b'\n', 0xff, 0xfe]);
+        cmd.assert().failure().code(2);
+    }
+
+    #[test]
+    fn test_pre_preprocessor_success() {
+        let mut cmd = Command::cargo_bin("iawk").expect("Could not find iawk.");
+        cmd.arg("--regexp=NEEDLE");
+        cmd.arg("--pre=tr a-z A-Z");
+        cmd.write_stdin(String::from("needle\n"));
+        cmd.assert().success();
+        let output = cmd.output().unwrap();
+        assert_eq!(b"NEEDLE\n", output.stdout.as_slice());
+    }
+
+    #[test]
+    fn test_pre_preprocessor_failure_exits_with_error() {
+        let mut cmd = Command::cargo_bin("iawk").expect("Could not find iawk.");
+        cmd.arg("--regexp=x");
+        cmd.arg("--pre=exit 3");
+        cmd.write_stdin(String::from("abc\n"));
+        cmd.assert().failure().code(2);
+    }
+
+    #[test]
+    fn test_fixed_strings_matches_literal_metacharacters() {
+        let mut cmd = Command::cargo_bin("iawk").expect("Could not find iawk.");
+        cmd.arg("--regexp=a.b");
+        cmd.arg("--fixed-strings");
+        cmd.write_stdin(String::from("a.b\naXb"));
+        cmd.assert().success();
+        let output = cmd.output().unwrap();
+        assert_eq!(b"a.b\n", output.stdout.as_slice());
+    }
+
+    #[test]
+    fn test_word_regexp_rejects_partial_word_match() {
+        let mut cmd = Command::cargo_bin("iawk").expect("Could not find iawk.");
+        cmd.arg("--regexp=cat");
+        cmd.arg("--word-regexp");
+        cmd.write_stdin(String::from("concatenate\na cat sat"));
+        cmd.assert().success();
+        let output = cmd.output().unwrap();
+        assert_eq!(b"a cat sat\n", output.stdout.as_slice());
+    }
+
+    #[test]
+    fn test_after_window_reextends_on_overlapping_match() {
+        let mut cmd = Command::cargo_bin("iawk").expect("Could not find iawk.");
+        cmd.arg("--regexp=MATCH");
+        cmd.arg("--after=2");
+        cmd.write_stdin(String::from("l1\nMATCH\nl3\nMATCH\nl5\nl6\nl7"));
+        cmd.assert().success();
+        let output = cmd.output().unwrap();
+        assert_eq!(b"MATCH\nl3\nMATCH\nl5\nl6\n", output.stdout.as_slice());
+    }
 }